@@ -1,13 +1,16 @@
+use std::collections::{HashMap, HashSet};
+
 use itertools::Itertools;
 
 use ra_syntax::{
-    ast::{self, AstNode},
+    ast::{self, AstNode, AttrsOwner, VisibilityOwner},
     Location,
     SourceFileNode,
     SyntaxKind,
     TextRange,
 };
 use ra_text_edit::{
+    AtomTextEdit,
     TextEdit,
     TextEditBuilder,
 };
@@ -18,7 +21,76 @@ use crate::{
     Severity,
 };
 
+/// A single syntactic lint, identified by a stable `code` used for config
+/// overrides (see `DiagnosticsConfig`) and `#[allow]`/`#[deny]` suppression.
+struct DiagnosticPass {
+    code: &'static str,
+    default_severity: Severity,
+    check: fn(&SourceFileNode, Severity) -> Vec<Diagnostic>,
+}
+
+fn passes() -> &'static [DiagnosticPass] {
+    &[
+        DiagnosticPass {
+            code: "unnecessary-braces",
+            default_severity: Severity::WeakWarning,
+            check: check_unnecessary_braces_in_use_statement,
+        },
+        DiagnosticPass {
+            code: "struct-shorthand",
+            default_severity: Severity::WeakWarning,
+            check: check_struct_shorthand_initialization,
+        },
+        DiagnosticPass {
+            code: "merge-imports",
+            default_severity: Severity::WeakWarning,
+            check: check_merge_imports,
+        },
+        DiagnosticPass {
+            code: "needless-return",
+            default_severity: Severity::WeakWarning,
+            check: check_needless_return,
+        },
+    ]
+}
+
+/// Controls which `DiagnosticPass`es run and at what `Severity`, so editors
+/// can expose lint toggles without recompiling.
+#[derive(Debug, Clone, Default)]
+pub struct DiagnosticsConfig {
+    disabled: HashSet<String>,
+    severity_overrides: HashMap<String, Severity>,
+}
+
+impl DiagnosticsConfig {
+    pub fn disable(&mut self, code: impl Into<String>) {
+        self.disabled.insert(code.into());
+    }
+
+    pub fn set_severity(&mut self, code: impl Into<String>, severity: Severity) {
+        self.severity_overrides.insert(code.into(), severity);
+    }
+
+    fn is_disabled(&self, code: &str) -> bool {
+        self.disabled.contains(code)
+    }
+
+    fn severity_for(&self, code: &str, default: Severity) -> Severity {
+        self.severity_overrides
+            .get(code)
+            .cloned()
+            .unwrap_or(default)
+    }
+}
+
 pub fn diagnostics(file: &SourceFileNode) -> Vec<Diagnostic> {
+    diagnostics_with_config(file, &DiagnosticsConfig::default())
+}
+
+pub fn diagnostics_with_config(
+    file: &SourceFileNode,
+    config: &DiagnosticsConfig,
+) -> Vec<Diagnostic> {
     fn location_to_range(location: Location) -> TextRange {
         match location {
             Location::Offset(offset) => TextRange::offset_len(offset, 1.into()),
@@ -37,12 +109,112 @@ pub fn diagnostics(file: &SourceFileNode) -> Vec<Diagnostic> {
         })
         .collect();
 
-    errors.extend(check_unnecessary_braces_in_use_statement(file));
-    errors.extend(check_struct_shorthand_initialization(file));
+    for pass in passes() {
+        if config.is_disabled(pass.code) {
+            continue;
+        }
+        let severity = config.severity_for(pass.code, pass.default_severity);
+        errors.extend((pass.check)(file, severity));
+    }
     errors
 }
 
-fn check_unnecessary_braces_in_use_statement(file: &SourceFileNode) -> Vec<Diagnostic> {
+/// Merges every fix for a given diagnostic `code` into a single `LocalEdit`,
+/// so e.g. "fix all unnecessary braces in file" applies as one undo step.
+/// Fixes are taken in offset order, and a fix whose range overlaps an earlier
+/// one (as with nested single-child brace lists) is left out, so the kept
+/// fixes' atomic edits are simply concatenated in offset order.
+pub fn fix_all(file: &SourceFileNode, code: &str) -> Option<LocalEdit> {
+    let pass = passes().iter().find(|pass| pass.code == code)?;
+    let mut fixes: Vec<Vec<AtomTextEdit>> = (pass.check)(file, pass.default_severity)
+        .into_iter()
+        .filter_map(|diagnostic| diagnostic.fix)
+        .map(|fix| fix.edit.into_atoms())
+        .collect();
+    if fixes.is_empty() {
+        return None;
+    }
+    fixes.sort_by_key(|fix| fix.iter().map(|atom| atom.delete.start()).min());
+
+    let mut atoms: Vec<AtomTextEdit> = Vec::new();
+    let mut covered_end = None;
+    for fix in fixes {
+        let start = fix.iter().map(|atom| atom.delete.start()).min();
+        if let (Some(start), Some(end)) = (start, covered_end) {
+            if start < end {
+                continue;
+            }
+        }
+        covered_end = fix.iter().map(|atom| atom.delete.end()).max().or(covered_end);
+        atoms.extend(fix);
+    }
+    atoms.sort_by_key(|atom| atom.delete.start());
+
+    let mut edit_builder = TextEditBuilder::new();
+    for atom in atoms {
+        if atom.delete.start() != atom.delete.end() {
+            edit_builder.delete(atom.delete);
+        }
+        if !atom.insert.is_empty() {
+            edit_builder.insert(atom.delete.start(), atom.insert);
+        }
+    }
+
+    Some(LocalEdit {
+        label: format!("Fix all '{}' occurrences", code),
+        edit: edit_builder.finish(),
+        cursor_position: None,
+    })
+}
+
+/// Adjusts `default_severity` according to `#[allow(code)]` / `#[warn(code)]` /
+/// `#[deny(code)]` attributes found on any ancestor of `node`, so a pass's
+/// lint `code` can be suppressed or escalated per-item. The closest enclosing
+/// attribute wins. Returns `None` if the diagnostic should be suppressed.
+///
+/// Registry codes are hyphenated (`"struct-shorthand"`) but lint attribute
+/// names are valid Rust identifiers, so the code is matched against attribute
+/// arguments with `-` translated to `_` (`#[allow(struct_shorthand)]`), and
+/// only as a whole identifier, not a substring.
+fn lint_severity(
+    node: ra_syntax::SyntaxNodeRef,
+    code: &str,
+    default_severity: Severity,
+) -> Option<Severity> {
+    let lint_name = code.replace('-', "_");
+    for ancestor in node.ancestors() {
+        for attr in ancestor.children().filter_map(ast::Attr::cast) {
+            let text = attr.syntax().text().to_string();
+            let inner = text
+                .trim_start_matches('#')
+                .trim_start_matches('!')
+                .trim()
+                .trim_start_matches('[')
+                .trim_end_matches(']');
+            let mut parts = inner.splitn(2, '(');
+            let level = parts.next().unwrap_or("").trim();
+            let args = parts.next().unwrap_or("").trim_end_matches(')');
+            let has_lint = args
+                .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .any(|word| word == lint_name);
+            if !has_lint {
+                continue;
+            }
+            match level {
+                "allow" => return None,
+                "deny" => return Some(Severity::Error),
+                "warn" => return Some(default_severity),
+                _ => continue,
+            }
+        }
+    }
+    Some(default_severity)
+}
+
+fn check_unnecessary_braces_in_use_statement(
+    file: &SourceFileNode,
+    default_severity: Severity,
+) -> Vec<Diagnostic> {
     let mut diagnostics = Vec::new();
     for use_tree_list in file
         .syntax()
@@ -51,6 +223,12 @@ fn check_unnecessary_braces_in_use_statement(file: &SourceFileNode) -> Vec<Diagn
     {
         if let Some((single_use_tree,)) = use_tree_list.use_trees().collect_tuple() {
             let range = use_tree_list.syntax().range();
+            let severity =
+                match lint_severity(use_tree_list.syntax(), "unnecessary-braces", default_severity)
+                {
+                    Some(severity) => severity,
+                    None => continue,
+                };
             let edit =
                 text_edit_for_remove_unnecessary_braces_with_self_in_use_statement(single_use_tree)
                     .unwrap_or_else(|| {
@@ -64,7 +242,7 @@ fn check_unnecessary_braces_in_use_statement(file: &SourceFileNode) -> Vec<Diagn
             diagnostics.push(Diagnostic {
                 range,
                 msg: format!("Unnecessary braces in use statement"),
-                severity: Severity::WeakWarning,
+                severity,
                 fix: Some(LocalEdit {
                     label: "Remove unnecessary braces".to_string(),
                     edit,
@@ -99,7 +277,10 @@ fn text_edit_for_remove_unnecessary_braces_with_self_in_use_statement(
     None
 }
 
-fn check_struct_shorthand_initialization(file: &SourceFileNode) -> Vec<Diagnostic> {
+fn check_struct_shorthand_initialization(
+    file: &SourceFileNode,
+    default_severity: Severity,
+) -> Vec<Diagnostic> {
     let mut diagnostics = Vec::new();
     for struct_lit in file.syntax().descendants().filter_map(ast::StructLit::cast) {
         if let Some(named_field_list) = struct_lit.named_field_list() {
@@ -108,6 +289,15 @@ fn check_struct_shorthand_initialization(file: &SourceFileNode) -> Vec<Diagnosti
                     let field_name = name_ref.syntax().text().to_string();
                     let field_expr = expr.syntax().text().to_string();
                     if field_name == field_expr {
+                        let severity = match lint_severity(
+                            named_field.syntax(),
+                            "struct-shorthand",
+                            default_severity,
+                        ) {
+                            Some(severity) => severity,
+                            None => continue,
+                        };
+
                         let mut edit_builder = TextEditBuilder::new();
                         edit_builder.delete(named_field.syntax().range());
                         edit_builder.insert(named_field.syntax().range().start(), field_name);
@@ -116,7 +306,7 @@ fn check_struct_shorthand_initialization(file: &SourceFileNode) -> Vec<Diagnosti
                         diagnostics.push(Diagnostic {
                             range: named_field.syntax().range(),
                             msg: format!("Shorthand struct initialization"),
-                            severity: Severity::WeakWarning,
+                            severity,
                             fix: Some(LocalEdit {
                                 label: "use struct shorthand initialization".to_string(),
                                 edit,
@@ -131,21 +321,354 @@ fn check_struct_shorthand_initialization(file: &SourceFileNode) -> Vec<Diagnosti
     diagnostics
 }
 
+/// Extends `check_unnecessary_braces_in_use_statement` with two more ways to
+/// tidy up `use` statements: merging adjacent sibling items that share a path
+/// prefix, and fully flattening nested single-child brace trees into a
+/// dotted path.
+fn check_merge_imports(file: &SourceFileNode, default_severity: Severity) -> Vec<Diagnostic> {
+    let mut diagnostics = check_merge_sibling_use_items(file, default_severity);
+    diagnostics.extend(check_flatten_nested_use(file, default_severity));
+    diagnostics
+}
+
+/// Finds runs of *adjacent* sibling `use` items that share a path prefix
+/// (e.g. `use a::b;` next to `use a::c;`) and proposes merging them into a
+/// single grouped import. Only plain items are merged: a `use` that is
+/// already bracketed (`use a::{b, c};`), carries an `as` alias, is a glob
+/// (`use a::*;`), or has attributes is left untouched, since none of those
+/// can be folded into a shared `{...}` list without losing information.
+fn check_merge_sibling_use_items(file: &SourceFileNode, default_severity: Severity) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut seen_parents = Vec::new();
+
+    for use_item in file.syntax().descendants().filter_map(ast::UseItem::cast) {
+        let parent = match use_item.syntax().parent() {
+            Some(parent) => parent,
+            None => continue,
+        };
+        if seen_parents.contains(&parent) {
+            continue;
+        }
+        seen_parents.push(parent);
+
+        let siblings: Vec<ast::UseItem> = parent.children().filter_map(ast::UseItem::cast).collect();
+        let infos: Vec<Option<MergeInfo>> =
+            siblings.iter().map(|&item| merge_info(item)).collect();
+
+        let mut i = 0;
+        while i < siblings.len() {
+            let mut j = i + 1;
+            if let Some(info) = &infos[i] {
+                let key = info.key();
+                while j < siblings.len()
+                    && siblings[j - 1]
+                        .syntax()
+                        .next_sibling()
+                        .and_then(|node| match node.kind() {
+                            SyntaxKind::WHITESPACE => node.next_sibling(),
+                            _ => Some(node),
+                        })
+                        == Some(siblings[j].syntax())
+                    && infos[j].as_ref().map(MergeInfo::key) == Some(key.clone())
+                {
+                    j += 1;
+                }
+                if j - i > 1 {
+                    let items = &siblings[i..j];
+                    let group_infos: Vec<&MergeInfo> =
+                        infos[i..j].iter().map(|info| info.as_ref().unwrap()).collect();
+                    if let Some(diagnostic) =
+                        merge_use_items(items, &group_infos, default_severity)
+                    {
+                        diagnostics.push(diagnostic);
+                    }
+                }
+            }
+            i = j.max(i + 1);
+        }
+    }
+
+    diagnostics
+}
+
+/// The parts of a plain `use` item relevant to merging: its visibility (if
+/// any), the path prefix shared with siblings, and its own last segment.
+/// `None` if the item can't be safely merged (see `check_merge_sibling_use_items`).
+#[derive(Clone, PartialEq, Eq)]
+struct MergeInfo {
+    visibility: Option<String>,
+    prefix: String,
+    tail: String,
+}
+
+impl MergeInfo {
+    fn key(&self) -> (Option<String>, String) {
+        (self.visibility.clone(), self.prefix.clone())
+    }
+}
+
+fn merge_info(use_item: ast::UseItem) -> Option<MergeInfo> {
+    if use_item.attrs().next().is_some() {
+        return None;
+    }
+    let use_tree = use_item.use_tree()?;
+    if use_tree.use_tree_list().is_some() || use_tree.alias().is_some() {
+        return None;
+    }
+    if use_tree.syntax().text().to_string().trim_end().ends_with('*') {
+        return None;
+    }
+
+    let path = use_tree.path()?;
+    let qualifier = path.qualifier()?;
+    let segment = path.segment()?;
+
+    Some(MergeInfo {
+        visibility: use_item.visibility().map(|vis| vis.syntax().text().to_string()),
+        prefix: qualifier.syntax().text().to_string(),
+        tail: segment.syntax().text().to_string(),
+    })
+}
+
+fn merge_use_items(
+    items: &[ast::UseItem],
+    infos: &[&MergeInfo],
+    default_severity: Severity,
+) -> Option<Diagnostic> {
+    let first_info = infos[0];
+    let visibility_prefix = match &first_info.visibility {
+        Some(vis) => format!("{} ", vis),
+        None => String::new(),
+    };
+    let tails: Vec<&str> = infos.iter().map(|info| info.tail.as_str()).collect();
+    let merged = format!(
+        "{}use {}::{{{}}};",
+        visibility_prefix,
+        first_info.prefix,
+        tails.join(", ")
+    );
+
+    let first = items[0];
+    let severity = lint_severity(first.syntax(), "merge-imports", default_severity)?;
+
+    let first_range = first.syntax().range();
+    let mut edit_builder = TextEditBuilder::new();
+    edit_builder.delete(first_range);
+    edit_builder.insert(first_range.start(), merged);
+    if items.len() > 1 {
+        // Adjacency was verified while grouping, so everything between the
+        // end of the first item and the end of the last — including the
+        // trailing items themselves and the whitespace between them — can be
+        // dropped as one contiguous range, leaving no blank lines behind.
+        let last = items.last().unwrap();
+        let tail_range = TextRange::from_to(first_range.end(), last.syntax().range().end());
+        edit_builder.delete(tail_range);
+    }
+
+    Some(Diagnostic {
+        range: first_range,
+        msg: format!("These {} `use` declarations can be merged", items.len()),
+        severity,
+        fix: Some(LocalEdit {
+            label: "Merge imports".to_string(),
+            edit: edit_builder.finish(),
+            cursor_position: None,
+        }),
+    })
+}
+
+/// Finds `use` trees that are a chain of single-child brace lists (`use
+/// a::{b::{c}};`) and proposes collapsing the whole chain into a dotted path
+/// (`use a::b::c;`). Unlike `check_unnecessary_braces_in_use_statement`,
+/// which removes one level of redundant braces per diagnostic, this flattens
+/// the entire nest in a single fix.
+fn check_flatten_nested_use(file: &SourceFileNode, default_severity: Severity) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for use_item in file.syntax().descendants().filter_map(ast::UseItem::cast) {
+        let use_tree = match use_item.use_tree() {
+            Some(use_tree) => use_tree,
+            None => continue,
+        };
+        let flattened = match flatten_single_chain(use_tree) {
+            Some(flattened) => flattened,
+            None => continue,
+        };
+
+        let range = use_tree.syntax().range();
+        let severity = match lint_severity(use_tree.syntax(), "merge-imports", default_severity) {
+            Some(severity) => severity,
+            None => continue,
+        };
+
+        let mut edit_builder = TextEditBuilder::new();
+        edit_builder.delete(range);
+        edit_builder.insert(range.start(), flattened);
+
+        diagnostics.push(Diagnostic {
+            range,
+            msg: format!("This `use` tree can be flattened"),
+            severity,
+            fix: Some(LocalEdit {
+                label: "Flatten nested use tree".to_string(),
+                edit: edit_builder.finish(),
+                cursor_position: None,
+            }),
+        });
+    }
+    diagnostics
+}
+
+/// If `use_tree` is a chain of *at least two* single-child brace lists,
+/// returns the fully flattened dotted path (e.g. `a::b::c` for
+/// `a::{b::{c}}`). A single brace level (`a::{b}`) is left for
+/// `check_unnecessary_braces_in_use_statement`, which already offers the same
+/// fix. Returns `None` if there is no such nesting, or if any link in the
+/// chain is a glob or carries an alias (neither of which can be represented
+/// as a plain path segment). A trailing `self` (`a::{b::{self}}`) is dropped
+/// rather than appended, matching how
+/// `check_unnecessary_braces_in_use_statement` turns `use a::{self};` into
+/// `use a;` — appending it verbatim would produce the invalid `a::b::self`.
+fn flatten_single_chain(use_tree: ast::UseTree) -> Option<String> {
+    let mut depth = 0;
+    let mut segments = Vec::new();
+
+    let mut current = use_tree;
+    loop {
+        if current.alias().is_some()
+            || current.syntax().text().to_string().trim_end().ends_with('*')
+        {
+            return None;
+        }
+        if let Some(path) = current.path() {
+            if !is_self_path(&path) {
+                segments.push(path.syntax().text().to_string());
+            }
+        }
+        match current.use_tree_list() {
+            Some(use_tree_list) => {
+                let (only_child,) = use_tree_list.use_trees().collect_tuple()?;
+                depth += 1;
+                current = only_child;
+            }
+            None => break,
+        }
+    }
+
+    if depth < 2 || segments.is_empty() {
+        return None;
+    }
+    Some(segments.join("::"))
+}
+
+fn is_self_path(path: &ast::Path) -> bool {
+    path.segment()
+        .and_then(|segment| segment.syntax().first_child())
+        .map(|child| child.kind() == SyntaxKind::SELF_KW)
+        .unwrap_or(false)
+}
+
+/// Whether `block` is the body of a function or closure, i.e. whether the
+/// block's own result (its tail statement/expression) is actually the value
+/// returned by something. A block used in statement position (an `if`, `loop`
+/// or other nested block) has no such guarantee, so rewriting a trailing
+/// `return` there would silently change what value flows out of the block.
+fn is_fn_or_closure_body(block: ast::Block) -> bool {
+    let parent = match block.syntax().parent() {
+        Some(parent) => parent,
+        None => return false,
+    };
+    // A closure's `{ ... }` body is a block expression wrapping the block.
+    let lambda_parent = match ast::BlockExpr::cast(parent) {
+        Some(_) => parent.parent().unwrap_or(parent),
+        None => parent,
+    };
+    ast::FnDef::cast(parent).is_some() || ast::LambdaExpr::cast(lambda_parent).is_some()
+}
+
+/// Finds a `return expr;` that is the last statement of a block whose value
+/// is used as the block's own result, and proposes dropping `return` and the
+/// trailing `;` to leave `expr` as the tail expression.
+fn check_needless_return(file: &SourceFileNode, default_severity: Severity) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for block in file.syntax().descendants().filter_map(ast::Block::cast) {
+        if block.expr().is_some() {
+            continue;
+        }
+        if !is_fn_or_closure_body(block) {
+            continue;
+        }
+        let last_stmt = match block.statements().last() {
+            Some(last_stmt) => last_stmt,
+            None => continue,
+        };
+        let expr_stmt = match ast::ExprStmt::cast(last_stmt.syntax()) {
+            Some(expr_stmt) => expr_stmt,
+            None => continue,
+        };
+        let return_expr = match expr_stmt.expr().and_then(|expr| ast::ReturnExpr::cast(expr.syntax()))
+        {
+            Some(return_expr) => return_expr,
+            None => continue,
+        };
+        let returned_expr = match return_expr.expr() {
+            Some(returned_expr) => returned_expr,
+            None => continue,
+        };
+
+        let severity = match lint_severity(return_expr.syntax(), "needless-return", default_severity)
+        {
+            Some(severity) => severity,
+            None => continue,
+        };
+
+        let return_kw_range = TextRange::from_to(
+            return_expr.syntax().range().start(),
+            returned_expr.syntax().range().start(),
+        );
+        let semicolon_range = TextRange::from_to(
+            return_expr.syntax().range().end(),
+            expr_stmt.syntax().range().end(),
+        );
+        let mut edit_builder = TextEditBuilder::new();
+        edit_builder.delete(return_kw_range);
+        edit_builder.delete(semicolon_range);
+
+        diagnostics.push(Diagnostic {
+            range: return_expr.syntax().range(),
+            msg: format!("Redundant return statement"),
+            severity,
+            fix: Some(LocalEdit {
+                label: "Remove redundant return statement".to_string(),
+                edit: edit_builder.finish(),
+                cursor_position: None,
+            }),
+        });
+    }
+    diagnostics
+}
+
 #[cfg(test)]
 mod tests {
     use crate::test_utils::assert_eq_text;
 
     use super::*;
 
-    fn check_not_applicable(code: &str, func: fn(file: &SourceFileNode) -> Vec<Diagnostic>) {
+    fn check_not_applicable(
+        code: &str,
+        func: fn(file: &SourceFileNode, Severity) -> Vec<Diagnostic>,
+    ) {
         let file = SourceFileNode::parse(code);
-        let diagnostics = func(&file);
+        let diagnostics = func(&file, Severity::WeakWarning);
         assert!(diagnostics.is_empty());
     }
 
-    fn check_apply(before: &str, after: &str, func: fn(file: &SourceFileNode) -> Vec<Diagnostic>) {
+    fn check_apply(
+        before: &str,
+        after: &str,
+        func: fn(file: &SourceFileNode, Severity) -> Vec<Diagnostic>,
+    ) {
         let file = SourceFileNode::parse(before);
-        let diagnostic = func(&file)
+        let diagnostic = func(&file, Severity::WeakWarning)
             .pop()
             .unwrap_or_else(|| panic!("no diagnostics for:\n{}\n", before));
         let fix = diagnostic.fix.unwrap();
@@ -263,4 +786,203 @@ fn main() {
             check_struct_shorthand_initialization,
         );
     }
+
+    #[test]
+    fn test_lint_attribute_suppression() {
+        check_not_applicable(
+            r#"
+struct A {
+    a: &'static str
+}
+
+#[allow(struct_shorthand)]
+fn main() {
+    let a = "haha";
+    A {
+        a: a
+    }
+}
+        "#,
+            check_struct_shorthand_initialization,
+        );
+
+        let file = SourceFileNode::parse(
+            r#"
+struct A {
+    a: &'static str
+}
+
+#[deny(struct_shorthand)]
+fn main() {
+    let a = "haha";
+    A {
+        a: a
+    }
+}
+        "#,
+        );
+        let diagnostic = check_struct_shorthand_initialization(&file, Severity::WeakWarning)
+            .pop()
+            .unwrap();
+        assert_eq!(diagnostic.severity, Severity::Error);
+    }
+
+    #[test]
+    fn test_fix_all_struct_shorthand() {
+        let before = r#"
+struct A {
+    a: &'static str,
+    b: &'static str,
+    c: &'static str
+}
+
+fn main() {
+    let a = "haha";
+    let b = "bb";
+    let c = "cc";
+    A {
+        a: a,
+        b: b,
+        c: c
+    }
+}
+        "#;
+        let after = r#"
+struct A {
+    a: &'static str,
+    b: &'static str,
+    c: &'static str
+}
+
+fn main() {
+    let a = "haha";
+    let b = "bb";
+    let c = "cc";
+    A {
+        a,
+        b,
+        c
+    }
+}
+        "#;
+        let file = SourceFileNode::parse(before);
+        let fix = fix_all(&file, "struct-shorthand").unwrap();
+        let actual = fix.edit.apply(before);
+        assert_eq_text!(after, &actual);
+    }
+
+    #[test]
+    fn test_check_merge_imports() {
+        check_not_applicable("use a::b;\nuse c::d;\n", check_merge_imports);
+        check_not_applicable("use a::{b, c};\n", check_merge_imports);
+        check_not_applicable("pub use a::b;\nuse a::c;\n", check_merge_imports);
+        check_not_applicable("use a::b as bb;\nuse a::c;\n", check_merge_imports);
+        check_not_applicable("use a::*;\nuse a::c;\n", check_merge_imports);
+        check_not_applicable("#[cfg(test)] use a::b;\nuse a::c;\n", check_merge_imports);
+
+        check_apply(
+            "use a::b;\nuse a::c;\n",
+            "use a::{b, c};\n",
+            check_merge_imports,
+        );
+        check_apply(
+            "use a::b;\nuse a::c;\nuse a::d;\n",
+            "use a::{b, c, d};\n",
+            check_merge_imports,
+        );
+        check_apply(
+            "pub use a::b;\npub use a::c;\n",
+            "pub use a::{b, c};\n",
+            check_merge_imports,
+        );
+    }
+
+    #[test]
+    fn test_check_flatten_nested_use() {
+        check_not_applicable("use a::b;\n", check_merge_imports);
+        check_not_applicable("use a::{b, c};\n", check_merge_imports);
+        // A single brace level is already handled by
+        // `check_unnecessary_braces_in_use_statement`; flattening should not
+        // offer a duplicate diagnostic for the same fix.
+        check_not_applicable("use a::{b};\n", check_merge_imports);
+        // Likewise a single-level `self` is the baseline lint's job, turning
+        // `use a::{self};` into `use a;` — flattening must not touch it and
+        // especially must not emit the invalid `use a::self;`.
+        check_not_applicable("use a::{self};\n", check_merge_imports);
+        check_not_applicable("use a::b::{self};\n", check_merge_imports);
+
+        check_apply("use a::{b::{c}};\n", "use a::b::c;\n", check_merge_imports);
+        check_apply("use a::{b::{self}};\n", "use a::b;\n", check_merge_imports);
+    }
+
+    #[test]
+    fn test_check_needless_return() {
+        check_not_applicable(
+            r#"
+fn foo() -> i32 {
+    let a = 1;
+    return a;
+    let b = 2;
+}
+        "#,
+            check_needless_return,
+        );
+
+        check_not_applicable(
+            r#"
+fn foo() {
+    return;
+}
+        "#,
+            check_needless_return,
+        );
+
+        check_not_applicable(
+            r#"
+fn foo() -> i32 {
+    1
+}
+        "#,
+            check_needless_return,
+        );
+
+        check_apply(
+            r#"
+fn foo() -> i32 {
+    let a = 1;
+    return a;
+}
+        "#,
+            r#"
+fn foo() -> i32 {
+    let a = 1;
+    a
+}
+        "#,
+            check_needless_return,
+        );
+
+        check_not_applicable(
+            r#"
+fn foo(c: bool) -> i32 {
+    if c {
+        return 1;
+    }
+    2
+}
+        "#,
+            check_needless_return,
+        );
+
+        check_not_applicable(
+            r#"
+fn foo() -> i32 {
+    loop {
+        return 1;
+    }
+}
+        "#,
+            check_needless_return,
+        );
+    }
 }